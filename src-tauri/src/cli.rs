@@ -0,0 +1,61 @@
+// Command-line overrides for the values this launcher otherwise derives
+// itself (free port, config directory, log file) or hard-codes. Lets CI,
+// servers, and scripted migrations point the embedded release wherever they
+// need without touching the binary.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+
+#[derive(Parser, Clone, Debug)]
+#[command(name = "clientats", about = "ClientATS desktop launcher")]
+pub struct Cli {
+    /// Pin the Phoenix server to this port instead of picking a free one.
+    #[arg(long)]
+    pub port: Option<u16>,
+
+    /// Path to the SQLite database file, overriding the one derived from
+    /// the config directory.
+    #[arg(long = "db-path")]
+    pub db_path: Option<PathBuf>,
+
+    /// Path to the uploads directory, overriding the one derived from the
+    /// config directory.
+    #[arg(long = "upload-dir")]
+    pub upload_dir: Option<PathBuf>,
+
+    /// Override the platform-specific config directory.
+    #[arg(long = "config-dir")]
+    pub config_dir: Option<PathBuf>,
+
+    /// Override where launcher logs are written.
+    #[arg(long = "log-file")]
+    pub log_file: Option<PathBuf>,
+
+    /// Minimum log level (error, warn, info, debug, trace). Overrides
+    /// `RUST_LOG` when set.
+    #[arg(long = "log-level")]
+    pub log_level: Option<String>,
+
+    /// Run (and migrate) the Phoenix release without creating a webview
+    /// window. Useful for CI, headless servers, and scripted migrations.
+    #[arg(long)]
+    pub headless: bool,
+
+    /// Don't hide the main window to the tray on close/minimize; let it
+    /// close/minimize like a normal window instead.
+    #[arg(long = "no-tray-hide")]
+    pub no_tray_hide: bool,
+
+    /// Command used to start the Phoenix dev server instead of expecting
+    /// `mix phx.server` to already be running (dev builds only).
+    #[cfg(debug_assertions)]
+    #[arg(long = "before-dev")]
+    pub before_dev: Option<String>,
+
+    /// Watch `lib/` and `assets/` for changes and restart the dev server /
+    /// reload the webview on change (dev builds only).
+    #[cfg(debug_assertions)]
+    #[arg(long)]
+    pub watch: bool,
+}
@@ -0,0 +1,147 @@
+// Exposes the embedded Phoenix server's lifecycle to the webview: a status
+// enum kept in Tauri state, `phoenix://*` events emitted as it transitions,
+// and `invoke`-able commands so the frontend can render a loading/error
+// screen instead of silently depending on the port being up.
+
+use std::path::PathBuf;
+use std::process::Child;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+#[cfg(not(debug_assertions))]
+use crate::supervisor::PhoenixEnv;
+
+#[derive(Clone, Copy, Serialize, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerStatus {
+    Starting,
+    Ready,
+    Down,
+    Restarting,
+}
+
+impl ServerStatus {
+    fn event_name(self) -> &'static str {
+        match self {
+            ServerStatus::Starting => "phoenix://starting",
+            ServerStatus::Ready => "phoenix://ready",
+            ServerStatus::Down => "phoenix://down",
+            ServerStatus::Restarting => "phoenix://restarting",
+        }
+    }
+
+    fn tray_label(self) -> &'static str {
+        match self {
+            ServerStatus::Starting => "starting",
+            ServerStatus::Ready => "ready",
+            ServerStatus::Down => "down",
+            ServerStatus::Restarting => "restarting",
+        }
+    }
+}
+
+pub struct ServerState {
+    pub port: Mutex<u16>,
+    pub db_path: Mutex<PathBuf>,
+    pub log_path: PathBuf,
+    pub status: Mutex<ServerStatus>,
+    #[cfg(not(debug_assertions))]
+    pub phoenix_child: Arc<Mutex<Option<Child>>>,
+    #[cfg(not(debug_assertions))]
+    pub phoenix_env: PhoenixEnv,
+    #[cfg(debug_assertions)]
+    pub dev_child: Arc<Mutex<Option<Child>>>,
+    #[cfg(debug_assertions)]
+    pub before_dev: Option<String>,
+}
+
+/// Updates the shared status, emits the matching `phoenix://*` event, and
+/// keeps the tray tooltip in sync.
+pub fn set_status(app: &AppHandle, state: &ServerState, status: ServerStatus) {
+    *state.status.lock().unwrap() = status;
+    let _ = app.emit(status.event_name(), status);
+    crate::tray::set_status(app, status.tray_label());
+    log::info!("Server status -> {:?}", status);
+}
+
+#[derive(Serialize)]
+pub struct ServerStatusPayload {
+    pub status: ServerStatus,
+    pub port: u16,
+}
+
+#[tauri::command]
+pub fn get_server_status(state: State<ServerState>) -> ServerStatusPayload {
+    ServerStatusPayload {
+        status: *state.status.lock().unwrap(),
+        port: *state.port.lock().unwrap(),
+    }
+}
+
+#[tauri::command]
+pub fn get_log_path(state: State<ServerState>) -> String {
+    state.log_path.to_string_lossy().to_string()
+}
+
+#[tauri::command]
+pub fn tail_log(state: State<ServerState>, n: usize) -> Vec<String> {
+    let content = std::fs::read_to_string(&state.log_path).unwrap_or_default();
+    let mut lines: Vec<String> = content.lines().rev().take(n).map(str::to_string).collect();
+    lines.reverse();
+    lines
+}
+
+#[cfg(not(debug_assertions))]
+#[tauri::command]
+pub fn restart_server(app: AppHandle, state: State<ServerState>) -> Result<(), String> {
+    set_status(&app, &state, ServerStatus::Restarting);
+
+    let restarted = crate::supervisor::restart_now(
+        &app,
+        &state.phoenix_child,
+        &state.phoenix_env,
+        &state.log_path,
+    );
+
+    if restarted {
+        set_status(&app, &state, ServerStatus::Ready);
+        Ok(())
+    } else {
+        set_status(&app, &state, ServerStatus::Down);
+        Err("Phoenix server did not come back up after restart".to_string())
+    }
+}
+
+#[cfg(debug_assertions)]
+#[tauri::command]
+pub fn restart_server(app: AppHandle, state: State<ServerState>) -> Result<(), String> {
+    let before_dev = state
+        .before_dev
+        .clone()
+        .ok_or_else(|| "No --before-dev command configured; restart the dev server yourself".to_string())?;
+
+    set_status(&app, &state, ServerStatus::Restarting);
+
+    {
+        let mut lock = state.dev_child.lock().unwrap();
+        if let Some(mut old_child) = lock.take() {
+            let _ = old_child.kill();
+            let _ = old_child.wait();
+        }
+        *lock = crate::dev_watcher::spawn_before_dev(&before_dev, &state.log_path);
+    }
+
+    let port = *state.port.lock().unwrap();
+    if crate::wait_for_server(port, &state.log_path) {
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.eval("window.location.reload()");
+        }
+        set_status(&app, &state, ServerStatus::Ready);
+        Ok(())
+    } else {
+        set_status(&app, &state, ServerStatus::Down);
+        Err("Dev server did not come back up after restart".to_string())
+    }
+}
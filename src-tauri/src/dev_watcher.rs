@@ -0,0 +1,110 @@
+// Dev-mode only: optionally owns the Phoenix dev server (the "before-dev"
+// command) and watches `lib/` and `assets/` for changes, debouncing them
+// into a server restart + webview reload so contributors get automatic
+// reloads without a separate `mix phx.server` + browser-refresh loop.
+
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::sync::mpsc::channel;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use notify::{RecursiveMode, Watcher};
+use tauri::{AppHandle, Manager};
+
+use crate::wait_for_server;
+
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Spawns the configured before-dev command (e.g. `"mix phx.server"`),
+/// splitting on whitespace. Returns `None` if the command is empty (nothing
+/// is logged), or after logging the error if it fails to start.
+pub fn spawn_before_dev(command_line: &str, log_path: &Path) -> Option<Child> {
+    let mut parts = command_line.split_whitespace();
+    let program = parts.next()?;
+
+    match Command::new(program).args(parts).spawn() {
+        Ok(child) => {
+            log::info!("before-dev '{}' started with PID: {}", command_line, child.id());
+            Some(child)
+        }
+        Err(e) => {
+            log::error!("Failed to start before-dev command '{}': {}", command_line, e);
+            None
+        }
+    }
+}
+
+/// Spawns a background thread that watches `lib/` and `assets/` next to the
+/// project root for changes, debounces them, and on each batch either
+/// restarts the before-dev server (if one is owned) or just re-waits for
+/// the port before reloading the main window.
+pub fn spawn_watcher(
+    app_handle: AppHandle,
+    port: u16,
+    before_dev_cmd: Option<String>,
+    dev_child: Arc<Mutex<Option<Child>>>,
+    log_path: PathBuf,
+) {
+    std::thread::spawn(move || {
+        let project_root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let watch_dirs = [project_root.join("../lib"), project_root.join("../assets")];
+
+        let (tx, rx) = channel();
+        let mut watcher = match notify::recommended_watcher(tx) {
+            Ok(w) => w,
+            Err(e) => {
+                log::error!("Failed to create file watcher: {}", e);
+                return;
+            }
+        };
+
+        let mut watched_any = false;
+        for dir in &watch_dirs {
+            if dir.exists() {
+                if let Err(e) = watcher.watch(dir, RecursiveMode::Recursive) {
+                    log::warn!("Failed to watch {:?}: {}", dir, e);
+                } else {
+                    log::info!("Watching {:?} for changes", dir);
+                    watched_any = true;
+                }
+            }
+        }
+        if !watched_any {
+            log::warn!("Dev watcher found no lib/ or assets/ directory to watch; exiting");
+            return;
+        }
+
+        loop {
+            // Block for the first event, then drain whatever else arrives
+            // within the debounce window so a save-all doesn't trigger a
+            // restart per file.
+            if rx.recv().is_err() {
+                return;
+            }
+            while rx.recv_timeout(DEBOUNCE).is_ok() {}
+
+            log::info!("Source change detected, restarting dev server...");
+
+            if let Some(cmd) = &before_dev_cmd {
+                let mut lock = dev_child.lock().unwrap();
+                if let Some(mut old_child) = lock.take() {
+                    let _ = old_child.kill();
+                    let _ = old_child.wait();
+                }
+                *lock = spawn_before_dev(cmd, &log_path);
+                drop(lock);
+            }
+
+            if !wait_for_server(port, &log_path) {
+                log::warn!("Dev server did not come back up after restart");
+                continue;
+            }
+
+            if let Some(window) = app_handle.get_webview_window("main") {
+                let _ = window.eval("window.location.reload()");
+                log::info!("Reloaded webview after dev server restart");
+            }
+        }
+    });
+}
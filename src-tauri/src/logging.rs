@@ -0,0 +1,116 @@
+// File-backed logging built on the `log` facade: level filtering via
+// `--log-level`/`RUST_LOG`, timestamped lines, and size-based rotation with
+// a capped number of retained files (`<log>.1`, `<log>.2`, ...) kept next to
+// the log file. The log file stays wherever the caller puts it; this module
+// does not move it under the config directory.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+const MAX_RETAINED_FILES: usize = 5;
+
+/// Logs to `path`, or best-effort to stderr if the file couldn't be opened
+/// (bad `--log-file`, read-only filesystem, disk full, ...) so a bad log
+/// path degrades logging instead of taking down the whole launcher.
+struct FileLogger {
+    path: PathBuf,
+    file: Mutex<Option<File>>,
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        // Level filtering is handled globally via `log::set_max_level`.
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
+        let line = format!("[{}] [{}] {}\n", timestamp, record.level(), record.args());
+
+        let mut file = self.file.lock().unwrap();
+        match file.as_mut() {
+            Some(file) => {
+                rotate_if_needed(&self.path, file);
+                let _ = file.write_all(line.as_bytes());
+                let _ = file.flush();
+
+                // Also print to stdout for dev mode.
+                #[cfg(debug_assertions)]
+                print!("{}", line);
+            }
+            None => eprint!("{}", line),
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(file) = self.file.lock().unwrap().as_mut() {
+            let _ = file.flush();
+        }
+    }
+}
+
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+fn rotate_if_needed(path: &Path, file: &mut File) {
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    if len < MAX_LOG_BYTES {
+        return;
+    }
+
+    for i in (1..MAX_RETAINED_FILES).rev() {
+        let from = rotated_path(path, i);
+        if from.exists() {
+            let _ = fs::rename(&from, rotated_path(path, i + 1));
+        }
+    }
+    let _ = fs::rename(path, rotated_path(path, 1));
+
+    if let Ok(new_file) = OpenOptions::new().create(true).append(true).open(path) {
+        *file = new_file;
+    }
+}
+
+/// Installs the global logger writing to `log_path`. `level_override` (from
+/// `--log-level`) wins over `RUST_LOG`, which wins over the `info` default.
+///
+/// If `log_path` can't be opened (a bad `--log-file`, a
+/// read-only filesystem, a full disk, ...), this falls back to a
+/// stderr-only logger rather than panicking the launcher before a window or
+/// tray icon ever appears.
+pub fn init(log_path: &Path, level_override: Option<LevelFilter>) {
+    let level = level_override
+        .or_else(|| std::env::var("RUST_LOG").ok().and_then(|s| s.parse().ok()))
+        .unwrap_or(LevelFilter::Info);
+
+    if let Some(parent) = log_path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_path)
+        .map_err(|e| eprintln!("Failed to open log file {:?}: {}; logging to stderr instead", log_path, e))
+        .ok();
+
+    let logger = FileLogger {
+        path: log_path.to_path_buf(),
+        file: Mutex::new(file),
+    };
+
+    log::set_boxed_logger(Box::new(logger)).expect("Failed to install logger");
+    log::set_max_level(level);
+}
@@ -2,19 +2,26 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use std::time::Duration;
-use std::fs::OpenOptions;
-use std::io::Write;
+use clap::Parser;
 use port_check::is_port_reachable;
 use tauri::Manager;
 use tauri::menu::{Menu, Submenu, MenuItem, PredefinedMenuItem};
 
+use std::sync::{Arc, Mutex};
+use std::process::Child;
 #[cfg(not(debug_assertions))]
 use std::net::TcpListener;
 #[cfg(not(debug_assertions))]
-use std::sync::{Arc, Mutex};
-#[cfg(not(debug_assertions))]
-use std::process::{Command, Child};
+use std::process::Command;
 
+#[cfg(not(debug_assertions))]
+mod supervisor;
+mod cli;
+mod commands;
+#[cfg(debug_assertions)]
+mod dev_watcher;
+mod logging;
+mod tray;
 
 const DEFAULT_PORT: u16 = 4000;
 const MAX_STARTUP_WAIT_SECS: u64 = 30;
@@ -22,20 +29,6 @@ const MAX_STARTUP_WAIT_SECS: u64 = 30;
 #[cfg(not(debug_assertions))]
 struct PhoenixProcess(Arc<Mutex<Option<Child>>>);
 
-// Helper function to write logs to a file
-fn log_to_file(log_path: &std::path::Path, message: &str) {
-    if let Ok(mut file) = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(log_path)
-    {
-        let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
-        let _ = writeln!(file, "[{}] {}", timestamp, message);
-    }
-    // Also print to stdout for dev mode
-    println!("{}", message);
-}
-
 // Get platform-specific config directory to match Elixir Platform module
 // Linux: ~/.config/clientats
 // macOS: ~/Library/Application Support/clientats
@@ -84,22 +77,24 @@ fn get_free_port() -> Option<u16> {
 }
 
 fn wait_for_server(port: u16, log_path: &std::path::Path) -> bool {
-    log_to_file(log_path, &format!("Waiting for Phoenix server on port {}...", port));
+    log::info!("Waiting for Phoenix server on port {}...", port);
     let start = std::time::Instant::now();
 
     while start.elapsed().as_secs() < MAX_STARTUP_WAIT_SECS {
         if is_port_reachable(format!("127.0.0.1:{}", port)) {
-            log_to_file(log_path, "Phoenix server is ready!");
+            log::info!("Phoenix server is ready!");
             return true;
         }
         std::thread::sleep(Duration::from_millis(500));
     }
 
-    log_to_file(log_path, &format!("Phoenix server failed to start within {} seconds", MAX_STARTUP_WAIT_SECS));
+    log::warn!("Phoenix server failed to start within {} seconds", MAX_STARTUP_WAIT_SECS);
     false
 }
 
 fn main() {
+    let cli = cli::Cli::parse();
+
     // Create log file path
     #[cfg(debug_assertions)]
     let log_path_val = std::path::PathBuf::from("/tmp/clientats-tauri-dev.log");
@@ -110,35 +105,62 @@ fn main() {
         temp_dir.join("clientats-tauri.log")
     };
 
-    let log_path = log_path_val.clone();
-    
+    let log_path = cli.log_file.clone().unwrap_or(log_path_val);
+
+    logging::init(
+        &log_path,
+        cli.log_level.as_deref().and_then(|s| s.parse().ok()),
+    );
+
     #[cfg(not(debug_assertions))]
     let phoenix_child: Arc<Mutex<Option<Child>>> = Arc::new(Mutex::new(None));
     #[cfg(not(debug_assertions))]
     let phoenix_child_clone = Arc::clone(&phoenix_child);
 
-    // In dev mode, just verify Phoenix is running - Tauri uses devUrl from config
+    // In dev mode, either spawn the Phoenix dev server ourselves (if
+    // --before-dev was given) or just verify it's already running - Tauri
+    // uses devUrl from config either way.
+    #[cfg(debug_assertions)]
+    let dev_port = cli.port.unwrap_or(DEFAULT_PORT);
+    #[cfg(debug_assertions)]
+    let dev_child: Arc<Mutex<Option<Child>>> = Arc::new(Mutex::new(None));
     #[cfg(debug_assertions)]
     {
-        log_to_file(&log_path, &format!("Development mode: Checking Phoenix on port {}...", DEFAULT_PORT));
-        log_to_file(&log_path, &format!("Log file: {}", log_path.display()));
-        if !wait_for_server(DEFAULT_PORT, &log_path) {
-            log_to_file(&log_path, &format!("ERROR: Phoenix server not running on port {}!", DEFAULT_PORT));
-            log_to_file(&log_path, "Start it with: mix phx.server");
+        if let Some(before_dev) = &cli.before_dev {
+            *dev_child.lock().unwrap() = dev_watcher::spawn_before_dev(before_dev, &log_path);
+        }
+
+        log::info!("Development mode: Checking Phoenix on port {}...", dev_port);
+        log::info!("Log file: {}", log_path.display());
+        if !wait_for_server(dev_port, &log_path) {
+            log::warn!("Phoenix server not running on port {}!", dev_port);
+            log::warn!("Start it with: mix phx.server");
             // In dev mode we don't exit, maybe it will start later or user will start it
         } else {
-            log_to_file(&log_path, "Phoenix is ready! Launching Tauri window...");
+            log::info!("Phoenix is ready! Launching Tauri window...");
         }
     }
 
     let builder = tauri::Builder::default();
-    
+
     #[cfg(not(debug_assertions))]
     let builder = builder.manage(PhoenixProcess(phoenix_child_clone));
 
+    let cli_for_setup = cli.clone();
+    let hide_to_tray = !cli.no_tray_hide;
+    #[cfg(debug_assertions)]
+    let dev_child_for_setup = Arc::clone(&dev_child);
+
     builder
         .plugin(tauri_plugin_shell::init())
+        .invoke_handler(tauri::generate_handler![
+            commands::get_server_status,
+            commands::restart_server,
+            commands::get_log_path,
+            commands::tail_log,
+        ])
         .setup(move |app| {
+            let cli = cli_for_setup.clone();
             // Create menu
             let handle = app.handle();
             
@@ -232,22 +254,63 @@ fn main() {
             // In dev mode, window loads devUrl automatically from tauri.conf.json
             #[cfg(debug_assertions)]
             {
-                log_to_file(&log_path, "Dev mode: Window will load from devUrl in config");
+                if cli.headless {
+                    log::info!("Headless mode: hiding webview window");
+                    // Tauri creates/shows the "main" window itself from
+                    // `tauri.conf.json`'s devUrl before `setup` ever runs, so
+                    // unlike the production branch there's no window
+                    // creation step to skip here - it has to be hidden
+                    // explicitly.
+                    if let Some(window) = app.get_webview_window("main") {
+                        let _ = window.hide();
+                    }
+                    return Ok(());
+                }
+
+                log::info!("Dev mode: Window will load from devUrl in config");
+
+                tray::create_tray(handle, tray::TrayContext { log_path: log_path.clone() })?;
+
+                app.manage(commands::ServerState {
+                    port: Mutex::new(dev_port),
+                    db_path: Mutex::new(std::path::PathBuf::new()),
+                    log_path: log_path.clone(),
+                    status: Mutex::new(commands::ServerStatus::Ready),
+                    dev_child: Arc::clone(&dev_child_for_setup),
+                    before_dev: cli.before_dev.clone(),
+                });
+                commands::set_status(handle, &app.state::<commands::ServerState>(), commands::ServerStatus::Ready);
+
                 let window = app.get_webview_window("main").expect("Failed to get main window");
-                let url = format!("http://localhost:{}", DEFAULT_PORT);
+                let window_for_close = window.clone();
+                window.on_window_event(move |event| {
+                    tray::handle_window_event(&window_for_close, event, hide_to_tray);
+                });
+                let url = format!("http://localhost:{}", dev_port);
                 window.navigate(url.parse().unwrap()).expect("Failed to navigate to Phoenix");
+
+                if cli.watch {
+                    dev_watcher::spawn_watcher(
+                        app.handle().clone(),
+                        dev_port,
+                        cli.before_dev.clone(),
+                        Arc::clone(&dev_child_for_setup),
+                        log_path.clone(),
+                    );
+                }
+
                 return Ok(());
             }
 
             // Production mode: Start embedded Phoenix server
             #[cfg(not(debug_assertions))]
             {
-                log_to_file(&log_path, "Production mode: Starting embedded Phoenix server");
-                log_to_file(&log_path, &format!("Log file location: {}", log_path.display()));
+                log::info!("Production mode: Starting embedded Phoenix server");
+                log::info!("Log file location: {}", log_path.display());
 
-                let port = get_free_port().unwrap_or(DEFAULT_PORT);
+                let port = cli.port.unwrap_or_else(|| get_free_port().unwrap_or(DEFAULT_PORT));
                 let url = format!("http://127.0.0.1:{}", port);
-                log_to_file(&log_path, &format!("Selected port: {}", port));
+                log::info!("Selected port: {}", port);
 
                 // Get the Phoenix release path
                 let phoenix_path = if cfg!(target_os = "macos") {
@@ -270,41 +333,47 @@ fn main() {
                         .join("clientats")
                 };
 
-                log_to_file(&log_path, &format!("Phoenix executable path: {:?}", phoenix_path));
+                log::info!("Phoenix executable path: {:?}", phoenix_path);
 
                 // Check if Phoenix executable exists
                 if !phoenix_path.exists() {
-                    log_to_file(&log_path, &format!("ERROR: Phoenix executable not found at {:?}", phoenix_path));
+                    log::error!("Phoenix executable not found at {:?}", phoenix_path);
                     panic!("Phoenix executable not found");
                 }
-                log_to_file(&log_path, "Phoenix executable found");
+                log::info!("Phoenix executable found");
 
                 // Get config directory (matches Elixir Platform module)
-                let config_dir = get_config_dir();
+                let config_dir = cli.config_dir.clone().unwrap_or_else(get_config_dir);
 
                 std::fs::create_dir_all(&config_dir)
                     .expect("Failed to create config directory");
 
-                // Database in db/ subdirectory to match Elixir convention
-                let db_dir = config_dir.join("db");
-                std::fs::create_dir_all(&db_dir)
-                    .expect("Failed to create db directory");
-
-                let db_path = db_dir.join("clientats.db");
-                log_to_file(&log_path, &format!("Database path: {:?}", db_path));
+                // Database in db/ subdirectory to match Elixir convention, unless overridden
+                let db_path = cli.db_path.clone().unwrap_or_else(|| {
+                    let db_dir = config_dir.join("db");
+                    std::fs::create_dir_all(&db_dir).expect("Failed to create db directory");
+                    db_dir.join("clientats.db")
+                });
+                if let Some(parent) = db_path.parent() {
+                    std::fs::create_dir_all(parent).expect("Failed to create database directory");
+                }
+                log::info!("Database path: {:?}", db_path);
 
-                // Create uploads directory
-                let upload_dir = config_dir.join("uploads");
+                // Create uploads directory, unless overridden
+                let upload_dir = cli.upload_dir.clone().unwrap_or_else(|| config_dir.join("uploads"));
                 std::fs::create_dir_all(&upload_dir)
                     .expect("Failed to create uploads directory");
-                log_to_file(&log_path, &format!("Upload directory: {:?}", upload_dir));
+                log::info!("Upload directory: {:?}", upload_dir);
 
                 // Step 1: Run migrations synchronously
-                log_to_file(&log_path, "Running database migrations...");
+                log::info!("Running database migrations...");
                 let migrate_result = Command::new(&phoenix_path)
                     .arg("eval")
                     .arg("Clientats.Release.migrate()")
-                    .env("DATABASE_PATH", db_path.to_str().unwrap())
+                    // `Command::env` takes anything `AsRef<OsStr>`, so this
+                    // carries non-UTF-8 paths straight through instead of
+                    // panicking on a user-supplied `--db-path`.
+                    .env("DATABASE_PATH", &db_path)
                     .env("MIX_ENV", "prod")
                     .output();
 
@@ -313,64 +382,85 @@ fn main() {
                         if !output.status.success() {
                             let stderr = String::from_utf8_lossy(&output.stderr);
                             let stdout = String::from_utf8_lossy(&output.stdout);
-                            log_to_file(&log_path, &format!("Migration stderr: {}", stderr));
-                            log_to_file(&log_path, &format!("Migration stdout: {}", stdout));
-                            log_to_file(&log_path, "Migration completed with warnings");
+                            log::warn!("Migration stderr: {}", stderr);
+                            log::warn!("Migration stdout: {}", stdout);
+                            log::warn!("Migration completed with warnings");
                         } else {
-                            log_to_file(&log_path, "Migrations completed successfully");
+                            log::info!("Migrations completed successfully");
                         }
                     }
                     Err(e) => {
-                        log_to_file(&log_path, &format!("Failed to run migrations: {}", e));
+                        log::error!("Failed to run migrations: {}", e);
                     }
                 }
 
-                // Step 2: Start Phoenix server
-                log_to_file(&log_path, "Starting Phoenix server...");
-                
-                // Use "exec" to replace the shell process with the Phoenix process.
-                // This ensures that child.kill() kills the Phoenix server directly
-                // rather than just the wrapper script.
-                let mut cmd = if cfg!(target_os = "windows") {
-                    let mut c = Command::new(&phoenix_path);
-                    c.arg("start");
-                    c
-                } else {
-                    let mut c = Command::new("sh");
-                    c.arg("-c");
-                    c.arg(format!("exec \"$1\" start"));
-                    c.arg("--");
-                    c.arg(&phoenix_path);
-                    c
+                let phoenix_env = supervisor::PhoenixEnv {
+                    phoenix_path: phoenix_path.clone(),
+                    port,
+                    db_path: db_path.clone(),
+                    upload_dir: upload_dir.clone(),
+                    restarting: Arc::new(std::sync::atomic::AtomicBool::new(false)),
                 };
 
-                cmd.env("PORT", port.to_string())
-                   .env("MIX_ENV", "prod")
-                   .env("PHX_SERVER", "true")
-                   .env("DATABASE_PATH", db_path.to_str().unwrap())
-                   .env("UPLOAD_DIR", upload_dir.to_str().unwrap());
+                app.manage(commands::ServerState {
+                    port: Mutex::new(port),
+                    db_path: Mutex::new(db_path.clone()),
+                    log_path: log_path.clone(),
+                    status: Mutex::new(commands::ServerStatus::Starting),
+                    phoenix_child: Arc::clone(&phoenix_child),
+                    phoenix_env: phoenix_env.clone(),
+                });
+                let state = app.state::<commands::ServerState>();
+
+                if !cli.headless {
+                    tray::create_tray(
+                        handle,
+                        tray::TrayContext {
+                            log_path: log_path.clone(),
+                            phoenix_child: Arc::clone(&phoenix_child),
+                            phoenix_env: phoenix_env.clone(),
+                        },
+                    )?;
+                }
+                commands::set_status(handle, &state, commands::ServerStatus::Starting);
+
+                // Step 2: Start Phoenix server
+                log::info!("Starting Phoenix server...");
 
-                match cmd.spawn() {
+                match supervisor::spawn_phoenix(&phoenix_env) {
                     Ok(child) => {
-                        log_to_file(&log_path, &format!("Phoenix server process started with PID: {}", child.id()));
+                        log::info!("Phoenix server process started with PID: {}", child.id());
                         let mut phoenix_child_lock = phoenix_child.lock().unwrap();
                         *phoenix_child_lock = Some(child);
                     }
                     Err(e) => {
-                        log_to_file(&log_path, &format!("Failed to spawn Phoenix server: {}", e));
+                        log::error!("Failed to spawn Phoenix server: {}", e);
                         panic!("Failed to start Phoenix server");
                     }
                 }
 
                 // Step 3: Wait for port to be reachable
-                log_to_file(&log_path, "Waiting for Phoenix server to be ready...");
+                log::info!("Waiting for Phoenix server to be ready...");
                 if !wait_for_server(port, &log_path) {
-                    log_to_file(&log_path, "FATAL: Phoenix server failed to start");
+                    commands::set_status(handle, &state, commands::ServerStatus::Down);
+                    log::error!("Phoenix server failed to start");
                     panic!("Phoenix server failed to start");
                 }
+                commands::set_status(handle, &state, commands::ServerStatus::Ready);
+
+                if cli.headless {
+                    log::info!("Headless mode: Phoenix server is running without a webview window");
+                    supervisor::spawn_supervisor(
+                        app.handle().clone(),
+                        Arc::clone(&phoenix_child),
+                        phoenix_env,
+                        log_path.clone(),
+                    );
+                    return Ok(());
+                }
 
                 // Step 4: Create window with URL
-                log_to_file(&log_path, &format!("Creating window with URL: {}", url));
+                log::info!("Creating window with URL: {}", url);
 
                 use tauri::WebviewUrl;
                 use tauri::WebviewWindowBuilder;
@@ -383,19 +473,32 @@ fn main() {
 
                 match window_builder.build() {
                     Ok(window) => {
-                        log_to_file(&log_path, "Window created successfully");
+                        log::info!("Window created successfully");
+                        let window_for_close = window.clone();
+                        window.on_window_event(move |event| {
+                            tray::handle_window_event(&window_for_close, event, hide_to_tray);
+                        });
                         match window.show() {
-                            Ok(_) => log_to_file(&log_path, "Window shown"),
-                            Err(e) => log_to_file(&log_path, &format!("Failed to show window: {}", e)),
+                            Ok(_) => log::info!("Window shown"),
+                            Err(e) => log::warn!("Failed to show window: {}", e),
                         }
                     }
                     Err(e) => {
-                        log_to_file(&log_path, &format!("Failed to create window: {}", e));
+                        log::error!("Failed to create window: {}", e);
                         panic!("Failed to create window");
                     }
                 }
 
-                log_to_file(&log_path, "Tauri setup complete");
+                // Step 5: Hand the process off to the supervisor so a crash
+                // mid-session gets detected and the release respawned.
+                supervisor::spawn_supervisor(
+                    app.handle().clone(),
+                    Arc::clone(&phoenix_child),
+                    phoenix_env,
+                    log_path.clone(),
+                );
+
+                log::info!("Tauri setup complete");
                 Ok(())
             }
         })
@@ -408,7 +511,15 @@ fn main() {
                     let phoenix_process = _app_handle.state::<PhoenixProcess>();
                     let mut child_lock = phoenix_process.0.lock().unwrap();
                     if let Some(mut child) = child_lock.take() {
-                        println!("Killing Phoenix server process...");
+                        log::info!("Killing Phoenix server process...");
+                        let _ = child.kill();
+                    }
+                }
+                #[cfg(debug_assertions)]
+                {
+                    let mut child_lock = dev_child.lock().unwrap();
+                    if let Some(mut child) = child_lock.take() {
+                        log::info!("Killing before-dev process...");
                         let _ = child.kill();
                     }
                 }
@@ -0,0 +1,209 @@
+// Supervises the embedded Phoenix release in production builds: watches the
+// child process and the port it should be listening on, and restarts the
+// release with backoff if either one goes away.
+
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use port_check::is_port_reachable;
+use tauri::{AppHandle, Manager};
+
+use crate::commands::{self, ServerStatus};
+use crate::wait_for_server;
+
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+const MAX_CONSECUTIVE_FAILURES: u32 = 3;
+const MAX_RESTARTS: u32 = 10;
+const INITIAL_BACKOFF_SECS: u64 = 1;
+const MAX_BACKOFF_SECS: u64 = 60;
+
+/// Everything needed to (re)spawn the Phoenix release with the exact same
+/// environment it was started with.
+#[derive(Clone)]
+pub struct PhoenixEnv {
+    pub phoenix_path: PathBuf,
+    pub port: u16,
+    pub db_path: PathBuf,
+    pub upload_dir: PathBuf,
+    /// Shared across every holder of this `PhoenixEnv` (the supervisor
+    /// thread, the tray menu, the `restart_server` command) so concurrent
+    /// callers of [`restart_now`] can't race each other's kill/spawn.
+    pub restarting: Arc<AtomicBool>,
+}
+
+/// Spawns the Phoenix release with the `PORT`/`DATABASE_PATH`/`UPLOAD_DIR`
+/// env Elixir's `Clientats.Release` expects. Shared by the initial startup
+/// in `main()` and by the supervisor's restart path so both take the exact
+/// same code path.
+pub fn spawn_phoenix(env: &PhoenixEnv) -> std::io::Result<Child> {
+    // Use "exec" to replace the shell process with the Phoenix process.
+    // This ensures that child.kill() kills the Phoenix server directly
+    // rather than just the wrapper script.
+    let mut cmd = if cfg!(target_os = "windows") {
+        let mut c = Command::new(&env.phoenix_path);
+        c.arg("start");
+        c
+    } else {
+        let mut c = Command::new("sh");
+        c.arg("-c");
+        c.arg("exec \"$1\" start");
+        c.arg("--");
+        c.arg(&env.phoenix_path);
+        c
+    };
+
+    cmd.env("PORT", env.port.to_string())
+        .env("MIX_ENV", "prod")
+        .env("PHX_SERVER", "true")
+        // `Command::env` takes anything `AsRef<OsStr>`, so this carries
+        // non-UTF-8 paths straight through instead of panicking on them.
+        .env("DATABASE_PATH", &env.db_path)
+        .env("UPLOAD_DIR", &env.upload_dir);
+
+    cmd.spawn()
+}
+
+/// Releases [`PhoenixEnv::restarting`] when dropped, so every early return
+/// in `restart_now` still clears the flag.
+struct RestartGuard<'a>(&'a AtomicBool);
+
+impl Drop for RestartGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Kills whatever is left of the old Phoenix process, respawns the release,
+/// waits for it to come back up, and re-navigates the main window. Shared
+/// by the supervisor's own restart path and by anything that triggers a
+/// restart manually (the tray menu, the `restart_server` command).
+///
+/// Only one restart runs at a time: if one is already in flight (tracked via
+/// `env.restarting`, shared by every caller), this is a no-op that returns
+/// `false` immediately rather than racing the in-flight kill/spawn.
+pub fn restart_now(
+    app_handle: &AppHandle,
+    child_handle: &Arc<Mutex<Option<Child>>>,
+    env: &PhoenixEnv,
+    log_path: &Path,
+) -> bool {
+    if env.restarting.swap(true, Ordering::SeqCst) {
+        log::warn!("Restart already in progress; ignoring concurrent restart request");
+        return false;
+    }
+    let _guard = RestartGuard(&env.restarting);
+
+    {
+        let mut lock = child_handle.lock().unwrap();
+        if let Some(mut old_child) = lock.take() {
+            let _ = old_child.kill();
+            let _ = old_child.wait();
+        }
+    }
+
+    match spawn_phoenix(env) {
+        Ok(child) => {
+            log::info!("Phoenix server respawned with PID: {}", child.id());
+            *child_handle.lock().unwrap() = Some(child);
+        }
+        Err(e) => {
+            log::error!("Failed to respawn Phoenix server: {}", e);
+            return false;
+        }
+    }
+
+    if !wait_for_server(env.port, log_path) {
+        return false;
+    }
+
+    if let Some(window) = app_handle.get_webview_window("main") {
+        let url = format!("http://127.0.0.1:{}", env.port);
+        match url.parse() {
+            Ok(parsed) => {
+                let _ = window.navigate(parsed);
+                log::info!("Re-navigated window to {}", url);
+            }
+            Err(e) => log::error!("Failed to parse restart URL {}: {}", url, e),
+        }
+    }
+
+    true
+}
+
+/// Spawns a background thread that periodically checks whether the Phoenix
+/// child is still alive and still answering on `env.port`. After
+/// `MAX_CONSECUTIVE_FAILURES` failed probes it kills whatever is left of the
+/// old process and respawns the release with exponential backoff, up to
+/// `MAX_RESTARTS` attempts, re-navigating the main window on success.
+pub fn spawn_supervisor(
+    app_handle: AppHandle,
+    child_handle: Arc<Mutex<Option<Child>>>,
+    env: PhoenixEnv,
+    log_path: PathBuf,
+) {
+    std::thread::spawn(move || {
+        let mut consecutive_failures = 0u32;
+        let mut restart_count = 0u32;
+        let mut backoff_secs = INITIAL_BACKOFF_SECS;
+
+        loop {
+            std::thread::sleep(HEALTH_CHECK_INTERVAL);
+
+            let exited = {
+                let mut lock = child_handle.lock().unwrap();
+                match lock.as_mut() {
+                    Some(child) => matches!(child.try_wait(), Ok(Some(_))),
+                    None => true,
+                }
+            };
+            let reachable = is_port_reachable(format!("127.0.0.1:{}", env.port));
+
+            if !exited && reachable {
+                consecutive_failures = 0;
+                backoff_secs = INITIAL_BACKOFF_SECS;
+                continue;
+            }
+
+            consecutive_failures += 1;
+            log::warn!(
+                "Health check failed ({}/{}): exited={}, reachable={}",
+                consecutive_failures, MAX_CONSECUTIVE_FAILURES, exited, reachable
+            );
+
+            if consecutive_failures < MAX_CONSECUTIVE_FAILURES {
+                continue;
+            }
+
+            let state = app_handle.state::<commands::ServerState>();
+
+            if restart_count >= MAX_RESTARTS {
+                commands::set_status(&app_handle, &state, ServerStatus::Down);
+                log::error!("Giving up after {} restarts; Phoenix server is down", MAX_RESTARTS);
+                return;
+            }
+
+            commands::set_status(&app_handle, &state, ServerStatus::Restarting);
+            log::warn!(
+                "Restarting Phoenix server (attempt {}/{}) in {}s...",
+                restart_count + 1,
+                MAX_RESTARTS,
+                backoff_secs
+            );
+            std::thread::sleep(Duration::from_secs(backoff_secs));
+
+            restart_count += 1;
+            if !restart_now(&app_handle, &child_handle, &env, &log_path) {
+                consecutive_failures = 0;
+                backoff_secs = (backoff_secs * 2).min(MAX_BACKOFF_SECS);
+                continue;
+            }
+
+            consecutive_failures = 0;
+            backoff_secs = INITIAL_BACKOFF_SECS;
+            commands::set_status(&app_handle, &state, ServerStatus::Ready);
+        }
+    });
+}
@@ -0,0 +1,148 @@
+// System tray icon: reflects the embedded Phoenix lifecycle in its tooltip
+// and icon glyph, offers a context menu to recover the window or inspect
+// logs without a terminal, and (unless `--no-tray-hide` is set) keeps the
+// app running in the tray when the window is closed or minimized.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+#[cfg(not(debug_assertions))]
+use std::process::Child;
+#[cfg(not(debug_assertions))]
+use std::sync::Mutex;
+
+use tauri::image::Image;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_shell::ShellExt;
+
+#[cfg(not(debug_assertions))]
+use crate::supervisor::PhoenixEnv;
+
+const STATUS_ICON_SIZE: u32 = 32;
+
+/// Solid-color square glyph for each server lifecycle phase, so the tray
+/// icon itself (not just the tooltip) reflects status at a glance: amber
+/// while starting/restarting, green once ready, red once down.
+fn status_icon(status: &str) -> Image<'static> {
+    let [r, g, b, a] = match status {
+        "starting" => [0xf5, 0xa6, 0x23, 0xff],
+        "ready" => [0x2e, 0xa0, 0x4a, 0xff],
+        "restarting" => [0xf5, 0xa6, 0x23, 0xff],
+        "down" => [0xdc, 0x26, 0x26, 0xff],
+        _ => [0x80, 0x80, 0x80, 0xff],
+    };
+    let mut rgba = Vec::with_capacity((STATUS_ICON_SIZE * STATUS_ICON_SIZE * 4) as usize);
+    for _ in 0..(STATUS_ICON_SIZE * STATUS_ICON_SIZE) {
+        rgba.extend_from_slice(&[r, g, b, a]);
+    }
+    Image::new_owned(rgba, STATUS_ICON_SIZE, STATUS_ICON_SIZE)
+}
+
+pub struct TrayContext {
+    pub log_path: PathBuf,
+    #[cfg(not(debug_assertions))]
+    pub phoenix_child: Arc<Mutex<Option<Child>>>,
+    #[cfg(not(debug_assertions))]
+    pub phoenix_env: PhoenixEnv,
+}
+
+pub fn create_tray(app: &AppHandle, ctx: TrayContext) -> tauri::Result<()> {
+    let show_i = MenuItem::with_id(app, "tray_show", "Show Window", true, None::<&str>)?;
+    #[cfg(not(debug_assertions))]
+    let restart_i = MenuItem::with_id(app, "tray_restart", "Restart Server", true, None::<&str>)?;
+    let open_log_i = MenuItem::with_id(app, "tray_open_log", "Open Log File", true, None::<&str>)?;
+    let open_data_i = MenuItem::with_id(app, "tray_open_data", "Open Data Folder", true, None::<&str>)?;
+    let quit_i = MenuItem::with_id(app, "tray_quit", "Quit", true, None::<&str>)?;
+
+    let mut items: Vec<&dyn tauri::menu::IsMenuItem<_>> = vec![&show_i];
+    #[cfg(not(debug_assertions))]
+    items.push(&restart_i);
+    items.push(&open_log_i);
+    items.push(&open_data_i);
+    let separator = PredefinedMenuItem::separator(app)?;
+    items.push(&separator);
+    items.push(&quit_i);
+
+    let menu = Menu::with_items(app, &items)?;
+
+    let log_path = ctx.log_path;
+    #[cfg(not(debug_assertions))]
+    let phoenix_child = ctx.phoenix_child;
+    #[cfg(not(debug_assertions))]
+    let phoenix_env = ctx.phoenix_env;
+
+    let tray_log_path = log_path.clone();
+    TrayIconBuilder::with_id("main")
+        .menu(&menu)
+        .tooltip("ClientATS - starting...")
+        .icon(status_icon("starting"))
+        .on_menu_event(move |app, event| match event.id.as_ref() {
+            "tray_show" => {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
+            }
+            "tray_open_log" => {
+                if let Err(e) = app.shell().open(tray_log_path.to_string_lossy(), None) {
+                    log::warn!("Failed to open log file from tray: {}", e);
+                }
+            }
+            "tray_open_data" => {
+                let data_dir = crate::get_config_dir();
+                if let Err(e) = app.shell().open(data_dir.to_string_lossy(), None) {
+                    log::warn!("Failed to open data folder from tray: {}", e);
+                }
+            }
+            "tray_quit" => app.exit(0),
+            #[cfg(not(debug_assertions))]
+            "tray_restart" => {
+                log::info!("Restart requested from tray");
+                let state = app.state::<crate::commands::ServerState>();
+                crate::commands::set_status(app, &state, crate::commands::ServerStatus::Restarting);
+                if crate::supervisor::restart_now(app, &phoenix_child, &phoenix_env, &tray_log_path) {
+                    crate::commands::set_status(app, &state, crate::commands::ServerStatus::Ready);
+                } else {
+                    crate::commands::set_status(app, &state, crate::commands::ServerStatus::Down);
+                    log::error!("Failed to restart Phoenix server from tray");
+                }
+            }
+            _ => {}
+        })
+        .build(app)?;
+
+    Ok(())
+}
+
+/// Updates the tray icon's tooltip and glyph to reflect the current server
+/// lifecycle phase (e.g. "starting", "ready", "down").
+pub fn set_status(app: &AppHandle, status: &str) {
+    if let Some(tray) = app.tray_by_id("main") {
+        let _ = tray.set_tooltip(Some(&format!("ClientATS - {}", status)));
+        let _ = tray.set_icon(Some(status_icon(status)));
+    }
+}
+
+/// Hides the main window to the tray instead of letting it close or
+/// minimize to the taskbar, when `hide_to_tray` is enabled (the default;
+/// disabled with `--no-tray-hide`).
+pub fn handle_window_event(window: &tauri::WebviewWindow, event: &tauri::WindowEvent, hide_to_tray: bool) {
+    if !hide_to_tray {
+        return;
+    }
+
+    match event {
+        tauri::WindowEvent::CloseRequested { api, .. } => {
+            api.prevent_close();
+            let _ = window.hide();
+        }
+        tauri::WindowEvent::Resized(_) => {
+            if window.is_minimized().unwrap_or(false) {
+                let _ = window.hide();
+            }
+        }
+        _ => {}
+    }
+}